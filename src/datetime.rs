@@ -0,0 +1,85 @@
+use chrono::{DateTime, TimeZone, Utc};
+use windows::core::PROPVARIANT;
+use windows::Win32::Foundation::FILETIME;
+use windows::Win32::System::Com::StructuredStorage::{
+    InitPropVariantFromFileTime, PropVariantClear, PropVariantToFileTime,
+};
+
+use crate::{FromPropVariant, ToPropVariant};
+
+/// Windows `FILETIME` counts 100ns intervals since 1601-01-01 UTC; Unix time
+/// counts seconds since 1970-01-01 UTC. This is the 1601->1970 offset
+/// expressed in the same 100ns ticks, used to translate between the two.
+const FILETIME_TO_UNIX_EPOCH_TICKS: i64 = 116_444_736_000_000_000;
+
+fn file_time_to_ticks(file_time: FILETIME) -> i64 {
+    ((file_time.dwHighDateTime as i64) << 32) | file_time.dwLowDateTime as i64
+}
+
+fn ticks_to_file_time(ticks: i64) -> FILETIME {
+    FILETIME {
+        dwLowDateTime: (ticks & 0xFFFF_FFFF) as u32,
+        dwHighDateTime: (ticks >> 32) as u32,
+    }
+}
+
+impl FromPropVariant for Option<DateTime<Utc>> {
+    fn from_prop_variant(mut prop: PROPVARIANT) -> Self {
+        unsafe {
+            if prop.is_empty() {
+                PropVariantClear(&mut prop).unwrap();
+                return None;
+            }
+
+            let file_time = PropVariantToFileTime(&prop, Default::default()).ok()?;
+            PropVariantClear(&mut prop).unwrap();
+
+            let unix_ticks = file_time_to_ticks(file_time) - FILETIME_TO_UNIX_EPOCH_TICKS;
+            let unix_seconds = unix_ticks.div_euclid(10_000_000);
+            let unix_nanos = unix_ticks.rem_euclid(10_000_000) * 100;
+            Utc.timestamp_opt(unix_seconds, unix_nanos as u32).single()
+        }
+    }
+}
+
+impl ToPropVariant for DateTime<Utc> {
+    fn to_prop_variant(&self) -> PROPVARIANT {
+        unsafe {
+            let unix_ticks = self.timestamp() * 10_000_000 + self.timestamp_subsec_nanos() as i64 / 100;
+            let file_time = ticks_to_file_time(unix_ticks + FILETIME_TO_UNIX_EPOCH_TICKS);
+            InitPropVariantFromFileTime(&file_time).unwrap()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_round_trip_through_file_time() {
+        let ticks = 133_700_000_000_000_000i64;
+        let file_time = ticks_to_file_time(ticks);
+        assert_eq!(file_time_to_ticks(file_time), ticks);
+    }
+
+    #[test]
+    fn epoch_offset_matches_known_unix_epoch_file_time() {
+        // 1970-01-01 00:00:00 UTC as FILETIME ticks is a well-known constant;
+        // subtracting our offset from it should land exactly on zero.
+        let unix_epoch_as_file_time_ticks = 116_444_736_000_000_000i64;
+        let file_time = ticks_to_file_time(unix_epoch_as_file_time_ticks);
+        let unix_ticks = file_time_to_ticks(file_time) - FILETIME_TO_UNIX_EPOCH_TICKS;
+        assert_eq!(unix_ticks, 0);
+    }
+
+    #[test]
+    fn known_file_time_decodes_to_expected_unix_seconds() {
+        // 2001-09-09 01:46:40 UTC (unix timestamp 1_000_000_000) expressed as
+        // FILETIME ticks, taken from the documented Windows epoch offset.
+        let file_time_ticks = FILETIME_TO_UNIX_EPOCH_TICKS + 1_000_000_000 * 10_000_000;
+        let file_time = ticks_to_file_time(file_time_ticks);
+        let unix_ticks = file_time_to_ticks(file_time) - FILETIME_TO_UNIX_EPOCH_TICKS;
+        assert_eq!(unix_ticks / 10_000_000, 1_000_000_000);
+    }
+}
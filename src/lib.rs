@@ -1,15 +1,29 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
+use std::collections::HashMap;
 use std::io::Error;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use windows::core::{HSTRING, PCWSTR, PWSTR};
-use windows::Win32::System::Com::StructuredStorage::{PropVariantClear, PROPVARIANT};
+use windows::core::{w, HSTRING, PCWSTR, PROPVARIANT, PWSTR};
+use windows::Win32::Foundation::FILETIME;
+use windows::Win32::System::Com::StructuredStorage::*;
 use windows::Win32::System::Com::*;
+use windows::Win32::System::Variant::{
+    VT_BOOL, VT_BSTR, VT_EMPTY, VT_FILETIME, VT_I4, VT_LPWSTR, VT_R8, VT_UI4, VT_UI8, VT_VECTOR,
+};
 use windows::Win32::UI::Shell::PropertiesSystem::*;
-use windows::*;
+
+mod scanner;
+pub use scanner::{PropertyScanner, ProgressData};
+
+#[cfg(feature = "chrono")]
+mod datetime;
 
 const DEFAULT_PROP_STRING: PCWSTR = w!("");
 const DEFAULT_PROP_U32: u32 = 0;
+const DEFAULT_PROP_I32: i32 = 0;
+const DEFAULT_PROP_U64: u64 = 0;
+const DEFAULT_PROP_F64: f64 = 0.0;
+const DEFAULT_PROP_BOOL: bool = false;
 
 pub trait FromPropVariant {
     fn from_prop_variant(prop: PROPVARIANT) -> Self;
@@ -18,7 +32,7 @@ pub trait FromPropVariant {
 impl FromPropVariant for String {
     fn from_prop_variant(mut prop: PROPVARIANT) -> Self {
         unsafe {
-            let prop_sting: PWSTR = PropVariantToStringWithDefault(&prop, DEFAULT_PROP_STRING);
+            let prop_sting: PCWSTR = PropVariantToStringWithDefault(&prop, DEFAULT_PROP_STRING);
             PropVariantClear(&mut prop).unwrap();
             prop_sting.to_string().unwrap_or("".to_string())
         }
@@ -35,6 +49,84 @@ impl FromPropVariant for u32 {
     }
 }
 
+impl FromPropVariant for i32 {
+    fn from_prop_variant(mut prop: PROPVARIANT) -> Self {
+        unsafe {
+            let prop_i32 = PropVariantToInt32WithDefault(&prop, DEFAULT_PROP_I32);
+            PropVariantClear(&mut prop).unwrap();
+            prop_i32
+        }
+    }
+}
+
+impl FromPropVariant for u64 {
+    fn from_prop_variant(mut prop: PROPVARIANT) -> Self {
+        unsafe {
+            let prop_u64 = PropVariantToUInt64WithDefault(&prop, DEFAULT_PROP_U64);
+            PropVariantClear(&mut prop).unwrap();
+            prop_u64
+        }
+    }
+}
+
+impl FromPropVariant for f64 {
+    fn from_prop_variant(mut prop: PROPVARIANT) -> Self {
+        unsafe {
+            let prop_f64 = PropVariantToDoubleWithDefault(&prop, DEFAULT_PROP_F64);
+            PropVariantClear(&mut prop).unwrap();
+            prop_f64
+        }
+    }
+}
+
+impl FromPropVariant for bool {
+    fn from_prop_variant(mut prop: PROPVARIANT) -> Self {
+        unsafe {
+            let prop_bool = PropVariantToBooleanWithDefault(&prop, DEFAULT_PROP_BOOL);
+            PropVariantClear(&mut prop).unwrap();
+            prop_bool.as_bool()
+        }
+    }
+}
+
+impl FromPropVariant for Vec<u32> {
+    fn from_prop_variant(prop: PROPVARIANT) -> Self {
+        let element_count = &mut 0;
+        unsafe {
+            let val_vec: *mut *mut u32 = &mut std::ptr::null_mut();
+            let result = PropVariantToUInt32VectorAlloc(&prop, val_vec, element_count);
+            if result.is_err() {
+                return Vec::new();
+            }
+            // `*val_vec` is allocated by COM's task allocator, not Rust's
+            // global allocator, so it must be copied out and freed with
+            // `CoTaskMemFree` rather than handed to `Vec::from_raw_parts`.
+            let owned = std::slice::from_raw_parts(*val_vec, *element_count as usize).to_vec();
+            CoTaskMemFree(Some(*val_vec as *const std::ffi::c_void));
+            owned
+        }
+    }
+}
+
+impl FromPropVariant for Vec<f64> {
+    fn from_prop_variant(prop: PROPVARIANT) -> Self {
+        let element_count = &mut 0;
+        unsafe {
+            let val_vec: *mut *mut f64 = &mut std::ptr::null_mut();
+            let result = PropVariantToDoubleVectorAlloc(&prop, val_vec, element_count);
+            if result.is_err() {
+                return Vec::new();
+            }
+            // `*val_vec` is allocated by COM's task allocator, not Rust's
+            // global allocator, so it must be copied out and freed with
+            // `CoTaskMemFree` rather than handed to `Vec::from_raw_parts`.
+            let owned = std::slice::from_raw_parts(*val_vec, *element_count as usize).to_vec();
+            CoTaskMemFree(Some(*val_vec as *const std::ffi::c_void));
+            owned
+        }
+    }
+}
+
 pub trait ToPropVariant {
     fn to_prop_variant(&self) -> PROPVARIANT;
 }
@@ -73,6 +165,48 @@ impl ToPropVariant for Vec<&str> {
     }
 }
 
+impl ToPropVariant for bool {
+    fn to_prop_variant(&self) -> PROPVARIANT {
+        PROPVARIANT::from(*self)
+    }
+}
+
+impl ToPropVariant for u32 {
+    fn to_prop_variant(&self) -> PROPVARIANT {
+        PROPVARIANT::from(*self)
+    }
+}
+
+impl ToPropVariant for i32 {
+    fn to_prop_variant(&self) -> PROPVARIANT {
+        PROPVARIANT::from(*self)
+    }
+}
+
+impl ToPropVariant for u64 {
+    fn to_prop_variant(&self) -> PROPVARIANT {
+        PROPVARIANT::from(*self)
+    }
+}
+
+impl ToPropVariant for f64 {
+    fn to_prop_variant(&self) -> PROPVARIANT {
+        PROPVARIANT::from(*self)
+    }
+}
+
+impl ToPropVariant for Vec<u32> {
+    fn to_prop_variant(&self) -> PROPVARIANT {
+        unsafe { InitPropVariantFromUInt32Vector(Some(self.as_slice())).unwrap() }
+    }
+}
+
+impl ToPropVariant for Vec<f64> {
+    fn to_prop_variant(&self) -> PROPVARIANT {
+        unsafe { InitPropVariantFromDoubleVector(Some(self.as_slice())).unwrap() }
+    }
+}
+
 pub struct PropVector {
     pub vector: Vec<String>,
 }
@@ -114,28 +248,136 @@ impl std::fmt::Display for PropVector {
     }
 }
 
+/// A property value whose Rust type wasn't known ahead of time, resolved at
+/// runtime from the PROPVARIANT's `vt` tag.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropValue {
+    Str(String),
+    StrVec(Vec<String>),
+    U32(u32),
+    I32(i32),
+    U64(u64),
+    F64(f64),
+    Bool(bool),
+    /// FILETIME ticks (100ns intervals since 1601-01-01 UTC), unconverted.
+    /// Enable the `chrono` feature and read the property directly as
+    /// `Option<chrono::DateTime<Utc>>` via `get_prop` for a decoded value.
+    DateTime(u64),
+    Empty,
+    /// `vt` tag that this crate doesn't yet know how to decode.
+    Unknown(u16),
+}
+
+impl PropValue {
+    /// `Empty`/`Unknown` have no faithful PROPVARIANT encoding, so writing
+    /// them back would silently blank the property. `set_props` rejects
+    /// them up front rather than let that happen.
+    pub fn is_writable(&self) -> bool {
+        !matches!(self, PropValue::Empty | PropValue::Unknown(_))
+    }
+}
+
+/// Raw `{fmtid}-pid` form used by `get_all_props` when a property key has no
+/// canonical name registered.
+fn fallback_prop_name(key: &PROPERTYKEY) -> String {
+    format!("{{{:?}}}-{}", key.fmtid, key.pid)
+}
+
+impl FromPropVariant for PropValue {
+    fn from_prop_variant(prop: PROPVARIANT) -> Self {
+        unsafe {
+            let vt = prop.as_raw().Anonymous.Anonymous.vt;
+            match vt {
+                _ if vt == VT_EMPTY.0 => {
+                    let mut prop = prop;
+                    PropVariantClear(&mut prop).unwrap();
+                    PropValue::Empty
+                }
+                _ if vt == VT_LPWSTR.0 || vt == VT_BSTR.0 => {
+                    PropValue::Str(String::from_prop_variant(prop))
+                }
+                _ if vt == VT_VECTOR.0 | VT_LPWSTR.0 => {
+                    PropValue::StrVec(PropVector::from_prop_variant(prop).vector)
+                }
+                _ if vt == VT_UI4.0 => PropValue::U32(u32::from_prop_variant(prop)),
+                _ if vt == VT_I4.0 => PropValue::I32(i32::from_prop_variant(prop)),
+                _ if vt == VT_UI8.0 => PropValue::U64(u64::from_prop_variant(prop)),
+                _ if vt == VT_R8.0 => PropValue::F64(f64::from_prop_variant(prop)),
+                _ if vt == VT_BOOL.0 => PropValue::Bool(bool::from_prop_variant(prop)),
+                _ if vt == VT_FILETIME.0 => {
+                    let file_time = prop.as_raw().Anonymous.Anonymous.Anonymous.filetime;
+                    PropValue::DateTime(
+                        ((file_time.dwHighDateTime as u64) << 32) | file_time.dwLowDateTime as u64,
+                    )
+                }
+                other => PropValue::Unknown(other),
+            }
+        }
+    }
+}
+
+impl ToPropVariant for PropValue {
+    fn to_prop_variant(&self) -> PROPVARIANT {
+        match self {
+            PropValue::Str(value) => value.to_prop_variant(),
+            PropValue::StrVec(value) => {
+                value.iter().map(String::as_str).collect::<Vec<&str>>().to_prop_variant()
+            }
+            PropValue::U32(value) => value.to_prop_variant(),
+            PropValue::I32(value) => value.to_prop_variant(),
+            PropValue::U64(value) => value.to_prop_variant(),
+            PropValue::F64(value) => value.to_prop_variant(),
+            PropValue::Bool(value) => value.to_prop_variant(),
+            PropValue::DateTime(ticks) => unsafe {
+                let file_time = FILETIME {
+                    dwLowDateTime: (*ticks & 0xFFFF_FFFF) as u32,
+                    dwHighDateTime: (*ticks >> 32) as u32,
+                };
+                InitPropVariantFromFileTime(&file_time).unwrap()
+            },
+            // `Empty`/`Unknown` have no faithful PROPVARIANT representation;
+            // callers must check `PropValue::is_writable` (as `set_props`
+            // does) before writing a value back, or they will silently blank
+            // the property instead of round-tripping it.
+            PropValue::Empty | PropValue::Unknown(_) => PROPVARIANT::default(),
+        }
+    }
+}
+
 pub struct FileProperties {
-    path: HSTRING,
+    path: PathBuf,
     props: IPropertyStore,
     context: IBindCtx,
 }
 
 impl FileProperties {
-    pub fn new(path: &str, flag: Option<GETPROPERTYSTOREFLAGS>) -> Result<Self, Error> {
-        if !Path::exists(Path::new(path)) {
+    pub fn new(path: impl AsRef<Path>, flag: Option<GETPROPERTYSTOREFLAGS>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        if !path.exists() {
             return Err(Error::new(
                 std::io::ErrorKind::NotFound,
-                format!("{} not found", path),
+                format!("{} not found", path.display()),
             ));
         }
-        let path: HSTRING = HSTRING::from(path);
+
+        // `SHGetPropertyStoreFromParsingName` rejects the verbatim `\\?\`
+        // extended-length prefix that `canonicalize` adds, so strip it here
+        // once rather than making every caller do it.
+        let canonical = path.canonicalize()?;
+        let path = match canonical.to_str() {
+            Some(canonical) => PathBuf::from(canonical.trim_start_matches(r"\\?\")),
+            None => canonical,
+        };
+        let path_hstring = HSTRING::from(path.as_path());
+
         unsafe {
-            CoInitializeEx(None, COINIT_APARTMENTTHREADED)?;
+            CoInitializeEx(None, COINIT_APARTMENTTHREADED).ok()?;
             let context = CreateBindCtx(0)?;
 
             let flag = flag.unwrap_or(GPS_READWRITE);
 
-            let props: IPropertyStore = SHGetPropertyStoreFromParsingName(&path, &context, flag)?;
+            let props: IPropertyStore =
+                SHGetPropertyStoreFromParsingName(&path_hstring, &context, flag)?;
 
             Ok(Self {
                 path,
@@ -145,6 +387,11 @@ impl FileProperties {
         }
     }
 
+    /// The canonicalized path this instance was opened against.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     pub fn get_prop_count(&self) -> u32 {
         unsafe { self.props.GetCount().unwrap() }
     }
@@ -175,6 +422,63 @@ impl FileProperties {
         unsafe { self.props.Commit()? };
         Ok(())
     }
+
+    /// Walks every property currently in the store, resolving each key back
+    /// to its canonical name, instead of probing known keys one at a time.
+    pub fn get_all_props(&self) -> Result<Vec<(String, PropValue)>, Error> {
+        let count = self.get_prop_count();
+        let mut all_props = Vec::with_capacity(count as usize);
+        unsafe {
+            for i in 0..count {
+                let mut prop_key = PROPERTYKEY::default();
+                self.props.GetAt(i, &mut prop_key)?;
+
+                // Vendor/custom keys commonly have no registered canonical
+                // name; fall back to the raw `{fmtid}-pid` form instead of
+                // aborting the whole enumeration over one unresolvable key.
+                let prop_name = match PSGetNameFromPropertyKey(&prop_key) {
+                    Ok(name) => {
+                        let resolved = name.to_string().unwrap_or_default();
+                        CoTaskMemFree(Some(name.as_ptr() as *const std::ffi::c_void));
+                        resolved
+                    }
+                    Err(_) => fallback_prop_name(&prop_key),
+                };
+
+                let prop_variant = self.props.GetValue(&prop_key)?;
+                all_props.push((prop_name, PropValue::from_prop_variant(prop_variant)));
+            }
+        }
+        Ok(all_props)
+    }
+
+    /// Stages every entry in `values` and commits them in a single
+    /// transaction. `IPropertyStore` changes are only persisted on
+    /// `Commit`, so if any `SetValue` fails this returns early without
+    /// committing, leaving the file's existing properties untouched.
+    pub fn set_props(&self, values: &HashMap<String, PropValue>) -> Result<(), Error> {
+        if let Some(prop_name) = values
+            .iter()
+            .find(|(_, value)| !value.is_writable())
+            .map(|(prop_name, _)| prop_name)
+        {
+            return Err(Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{} is PropValue::Empty/Unknown and cannot be written", prop_name),
+            ));
+        }
+
+        for (prop_name, value) in values {
+            self.set_prop(prop_name, value.clone())?;
+        }
+        self.commit()
+    }
+
+    /// Alias for [`Self::set_props`] for callers that prefer a config-style
+    /// entry point for applying a batch of properties.
+    pub fn apply(&self, values: HashMap<String, PropValue>) -> Result<(), Error> {
+        self.set_props(&values)
+    }
 }
 
 impl Drop for FileProperties {
@@ -196,21 +500,29 @@ mod tests {
 
     const TEST_FILE_DIR: &str = r#".\test"#;
 
-    fn get_full_path(file_name: &str) -> String {
-        Path::new(file_name)
-            .canonicalize()
+    fn enumerate_test_files() -> impl Iterator<Item = PathBuf> {
+        fs::read_dir(TEST_FILE_DIR)
             .unwrap()
-            .into_os_string()
-            .into_string()
-            .unwrap()
-            .replace(r#"\\?\"#, "")
+            .map(|x| x.unwrap().path())
+            .filter(|x| x.extension().is_some_and(|ext| ext == "mp4"))
     }
 
-    fn enumerate_test_files() -> impl Iterator<Item = String> {
-        fs::read_dir(get_full_path(TEST_FILE_DIR))
-            .unwrap()
-            .map(|x| x.unwrap().path().to_str().unwrap().to_string())
-            .filter(|x| x.ends_with(".mp4"))
+    #[test]
+    fn prop_value_is_writable_rejects_empty_and_unknown() {
+        assert!(!PropValue::Empty.is_writable());
+        assert!(!PropValue::Unknown(1234).is_writable());
+        assert!(PropValue::U32(1).is_writable());
+        assert!(PropValue::Str("x".to_string()).is_writable());
+    }
+
+    #[test]
+    fn fallback_prop_name_uses_fmtid_and_pid() {
+        let key = PROPERTYKEY {
+            fmtid: windows::core::GUID::zeroed(),
+            pid: 5,
+        };
+        let name = fallback_prop_name(&key);
+        assert!(name.ends_with("-5"));
     }
 
     #[test]
@@ -218,7 +530,7 @@ mod tests {
         for file in enumerate_test_files() {
             let props = FileProperties::new(&file, None).unwrap();
             let id: String = props.get_prop("System.Media.UniqueFileIdentifier").unwrap();
-            if file.contains("without") {
+            if file.to_string_lossy().contains("without") {
                 assert!(id.is_empty());
             } else {
                 assert!(!id.is_empty());
@@ -228,16 +540,16 @@ mod tests {
 
     #[test]
     fn sets_props() {
-        let raw_test_video_path = format!("{}\\{}", TEST_FILE_DIR, "video_without_properties.mp4");
-        let raw_test_video_path = Path::new(&raw_test_video_path).canonicalize().unwrap();
-        let full_test_dir_path = raw_test_video_path.parent().unwrap();
-        let full_test_file_path = Path::join(full_test_dir_path, "new_test_video.mp4")
-            .into_os_string()
-            .into_string()
+        let raw_test_video_path =
+            Path::new(TEST_FILE_DIR).join("video_without_properties.mp4");
+        let full_test_file_path = raw_test_video_path
+            .canonicalize()
             .unwrap()
-            .replace(r#"\\?\"#, "");
+            .parent()
+            .unwrap()
+            .join("new_test_video.mp4");
 
-        fs::copy(raw_test_video_path, &full_test_file_path).unwrap();
+        fs::copy(&raw_test_video_path, &full_test_file_path).unwrap();
 
         let test_id = "this_is_the_test_id";
 
@@ -259,6 +571,95 @@ mod tests {
         fs::remove_file(&full_test_file_path).unwrap();
     }
 
+    #[test]
+    fn set_props_is_all_or_nothing() {
+        let raw_test_video_path =
+            Path::new(TEST_FILE_DIR).join("video_without_properties.mp4");
+        let full_test_file_path = raw_test_video_path
+            .canonicalize()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join("rollback_test_video.mp4");
+
+        fs::copy(&raw_test_video_path, &full_test_file_path).unwrap();
+
+        let test_id = "should_not_be_persisted";
+        let mut values = HashMap::new();
+        values.insert(
+            "System.Media.UniqueFileIdentifier".to_string(),
+            PropValue::Str(test_id.to_string()),
+        );
+        // An unwritable entry anywhere in the batch should block the whole
+        // commit, including the otherwise-valid entry above.
+        values.insert("System.Bogus.NotAProperty".to_string(), PropValue::Unknown(0));
+
+        {
+            let props = FileProperties::new(&full_test_file_path, Some(GPS_READWRITE)).unwrap();
+            assert!(props.set_props(&values).is_err());
+        }
+
+        {
+            let props = FileProperties::new(&full_test_file_path, None).unwrap();
+            let id: String = props.get_prop("System.Media.UniqueFileIdentifier").unwrap();
+            assert_ne!(id, test_id);
+        }
+
+        fs::remove_file(&full_test_file_path).unwrap();
+    }
+
+    #[test]
+    fn round_trips_scalar_and_vector_props() {
+        let raw_test_video_path =
+            Path::new(TEST_FILE_DIR).join("video_without_properties.mp4");
+        let full_test_file_path = raw_test_video_path
+            .canonicalize()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join("roundtrip_test_video.mp4");
+
+        fs::copy(&raw_test_video_path, &full_test_file_path).unwrap();
+
+        {
+            let props = FileProperties::new(&full_test_file_path, Some(GPS_READWRITE)).unwrap();
+            props.set_prop("System.Generic.Boolean", true).unwrap();
+            props.set_prop("System.Generic.Int32", -42i32).unwrap();
+            props.set_prop("System.Generic.Integer", 7u32).unwrap();
+            props.set_prop("System.Generic.UInt64", 9_000_000_000u64).unwrap();
+            props.set_prop("System.Generic.Double", 3.5f64).unwrap();
+            props
+                .set_prop("System.Generic.IntegerVector", vec![1u32, 2, 3])
+                .unwrap();
+            props
+                .set_prop("System.Generic.DoubleVector", vec![1.5f64, 2.5])
+                .unwrap();
+            props.commit().unwrap();
+        }
+
+        {
+            let props = FileProperties::new(&full_test_file_path, None).unwrap();
+            assert!(props.get_prop::<bool>("System.Generic.Boolean").unwrap());
+            assert_eq!(props.get_prop::<i32>("System.Generic.Int32").unwrap(), -42);
+            assert_eq!(props.get_prop::<u32>("System.Generic.Integer").unwrap(), 7);
+            assert_eq!(
+                props.get_prop::<u64>("System.Generic.UInt64").unwrap(),
+                9_000_000_000
+            );
+            assert_eq!(props.get_prop::<f64>("System.Generic.Double").unwrap(), 3.5);
+            assert_eq!(
+                props.get_prop::<Vec<u32>>("System.Generic.IntegerVector").unwrap(),
+                vec![1, 2, 3]
+            );
+            assert_eq!(
+                props.get_prop::<Vec<f64>>("System.Generic.DoubleVector").unwrap(),
+                vec![1.5, 2.5]
+            );
+        }
+
+        fs::remove_file(&full_test_file_path).unwrap();
+    }
+
     #[bench]
     fn bench_get_string_prop(b: &mut Bencher) {
         let files = enumerate_test_files().collect::<Vec<_>>();
@@ -269,7 +670,7 @@ mod tests {
                     .get_prop::<String>("System.Media.UniqueFileIdentifier")
                     .unwrap();
 
-                if file.contains("without") {
+                if file.to_string_lossy().contains("without") {
                     assert!(id.is_empty());
                 } else {
                     assert!(!id.is_empty());
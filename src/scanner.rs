@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use crossbeam_channel::Sender;
+use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED};
+
+use crate::{FileProperties, PropValue};
+
+/// Files-scanned / total counts, sent after each file so a GUI can render a
+/// progress bar without polling.
+#[derive(Debug, Clone, Copy)]
+pub struct ProgressData {
+    pub files_scanned: usize,
+    pub total: usize,
+}
+
+/// Recursively walks a directory tree and extracts a caller-chosen set of
+/// property names from every file it finds, spreading the work across a
+/// configurable thread pool.
+pub struct PropertyScanner {
+    thread_count: usize,
+}
+
+impl PropertyScanner {
+    pub fn new(thread_count: usize) -> Self {
+        Self {
+            thread_count: thread_count.max(1),
+        }
+    }
+
+    /// Scans `root` for `prop_names`, returning each file's path alongside
+    /// the properties that were successfully read from it.
+    ///
+    /// Checks `stop` between files so callers can cancel a long-running scan,
+    /// and reports progress on `progress` if given.
+    pub fn scan(
+        &self,
+        root: impl AsRef<Path>,
+        prop_names: &[String],
+        stop: Arc<AtomicBool>,
+        progress: Option<Sender<ProgressData>>,
+    ) -> Result<Vec<(PathBuf, HashMap<String, PropValue>)>, Error> {
+        let files = Self::collect_files(root.as_ref());
+        let total = files.len();
+        let files_scanned = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let chunk_size = total.div_ceil(self.thread_count).max(1);
+        let results: Vec<_> = thread::scope(|scope| {
+            files
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let stop = Arc::clone(&stop);
+                    let progress = progress.clone();
+                    let files_scanned = Arc::clone(&files_scanned);
+                    let prop_names = prop_names.to_vec();
+                    scope.spawn(move || {
+                        Self::scan_chunk(chunk, &prop_names, &stop, &progress, &files_scanned, total)
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Result<Vec<_>, Error>>()
+        })?;
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    fn scan_chunk(
+        chunk: &[PathBuf],
+        prop_names: &[String],
+        stop: &AtomicBool,
+        progress: &Option<Sender<ProgressData>>,
+        files_scanned: &std::sync::atomic::AtomicUsize,
+        total: usize,
+    ) -> Result<Vec<(PathBuf, HashMap<String, PropValue>)>, Error> {
+        // Each worker owns its own COM apartment: `FileProperties` is bound
+        // to the thread that created it, so the pool initializes COM once
+        // per worker rather than once globally.
+        unsafe {
+            CoInitializeEx(None, COINIT_APARTMENTTHREADED).ok()?;
+        }
+
+        let mut results = Vec::with_capacity(chunk.len());
+        for path in chunk {
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+
+            if let Ok(props) = FileProperties::new(path, None) {
+                let mut found = HashMap::with_capacity(prop_names.len());
+                for prop_name in prop_names {
+                    if let Ok(value) = props.get_prop::<PropValue>(prop_name) {
+                        found.insert(prop_name.clone(), value);
+                    }
+                }
+                results.push((path.clone(), found));
+            }
+
+            let scanned = files_scanned.fetch_add(1, Ordering::Relaxed) + 1;
+            if let Some(sender) = progress {
+                let _ = sender.send(ProgressData {
+                    files_scanned: scanned,
+                    total,
+                });
+            }
+        }
+
+        unsafe {
+            CoUninitialize();
+        }
+
+        Ok(results)
+    }
+
+    /// Recursively lists every regular file under `dir`. Entries that
+    /// `FileType::is_symlink` reports (symlinks and junctions) are skipped
+    /// rather than followed, since a cycle among them would otherwise
+    /// recurse forever. Other reparse points, such as cloud-sync
+    /// placeholders, are not skipped and are listed or descended into like
+    /// ordinary files and directories.
+    fn collect_files(dir: &Path) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        let Ok(entries) = fs::read_dir(dir) else {
+            return files;
+        };
+
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_symlink() {
+                continue;
+            }
+
+            let path = entry.path();
+            if file_type.is_dir() {
+                files.extend(Self::collect_files(&path));
+            } else {
+                files.push(path);
+            }
+        }
+
+        files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    use std::os::unix::fs::symlink;
+    #[cfg(windows)]
+    use std::os::windows::fs::symlink_dir as symlink;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("windows-properties-system-test-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn new_clamps_zero_thread_count_to_one() {
+        assert_eq!(PropertyScanner::new(0).thread_count, 1);
+        assert_eq!(PropertyScanner::new(4).thread_count, 4);
+    }
+
+    #[test]
+    fn collect_files_finds_nested_files() {
+        let root = scratch_dir("nested");
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::write(root.join("top.txt"), b"").unwrap();
+        fs::write(root.join("a/mid.txt"), b"").unwrap();
+        fs::write(root.join("a/b/bottom.txt"), b"").unwrap();
+
+        let mut files = PropertyScanner::collect_files(&root);
+        files.sort();
+
+        assert_eq!(files.len(), 3);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn collect_files_does_not_follow_a_symlink_cycle() {
+        let root = scratch_dir("cycle");
+        fs::create_dir_all(root.join("a")).unwrap();
+        fs::write(root.join("a/file.txt"), b"").unwrap();
+        symlink(&root, root.join("a/loop")).unwrap();
+
+        let files = PropertyScanner::collect_files(&root);
+
+        assert_eq!(files, vec![root.join("a/file.txt")]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}